@@ -0,0 +1,228 @@
+#![allow(non_snake_case)]
+//! Pedersen commitments over the BN curve and a proof bridging an ElGamal
+//! `Ciphertext` to a `Commitment` of the same value.
+//!
+//! The commitment generator `H` is derived by hashing `G1::one()` to a curve
+//! point, so its discrete logarithm with respect to `G` is unknown and the
+//! commitments are binding as well as hiding.
+
+use bn::*;
+use rand::Rng;
+use sha3::{Digest, Keccak256};
+
+use crate::ciphertext::Ciphertext;
+use crate::errors::ProofError;
+use crate::public::{challenge_from_points, PublicKey};
+
+lazy_static::lazy_static! {
+    // The second generator is deterministic, so derive it once and reuse it
+    // across every commitment and proof rather than rehashing per call.
+    static ref SECOND_GENERATOR: G1 = compute_second_generator();
+}
+
+/// The second, independent Pedersen generator `H`, obtained by hashing the
+/// encoding of `G1::one()` with Keccak256 and incrementing a counter until the
+/// digest is the `x`-coordinate of a curve point.
+pub fn second_generator() -> G1 {
+    *SECOND_GENERATOR
+}
+
+fn compute_second_generator() -> G1 {
+    let base = into_bytes(G1::one());
+    let mut counter: u32 = 0;
+    loop {
+        let digest = Keccak256::new()
+            .chain(&base)
+            .chain(counter.to_be_bytes())
+            .result();
+        if let Ok(x) = Fq::from_slice(&digest[..]) {
+            // BN curve equation `y^2 = x^3 + 3`.
+            let rhs = x * x * x + Fq::from_str("3").unwrap();
+            if let Some(y) = rhs.sqrt() {
+                if let Ok(affine) = AffineG1::new(x, y) {
+                    return affine.into();
+                }
+            }
+        }
+        counter += 1;
+    }
+}
+
+fn into_bytes(point: G1) -> Vec<u8> {
+    let affine = AffineG1::from_jacobian(point).unwrap();
+    bincode::rustc_serialize::encode(&affine, bincode::SizeLimit::Infinite).unwrap()
+}
+
+/// A Pedersen commitment `value·G + opening·H`.
+#[derive(Copy, Clone, Debug)]
+pub struct Commitment(G1);
+
+impl Commitment {
+    /// Get the underlying commitment point.
+    pub fn get_point(&self) -> G1 {
+        self.0
+    }
+}
+
+impl PartialEq for Commitment {
+    fn eq(&self, other: &Commitment) -> bool {
+        self.0 == other.0
+    }
+}
+
+/// Commits to `value` with blinding `opening`, producing `value·G + opening·H`.
+pub fn commit(value: Fr, opening: Fr) -> Commitment {
+    Commitment(G1::one() * value + second_generator() * opening)
+}
+
+/// Proves that `ciphertext = (c1 = r·G, c2 = m·G + r·pk)` and `commitment =
+/// m·G + opening·H` encode the same scalar `m`. The prover knows `m`, `r` and
+/// `opening`; it announces `A = k_r·G`, `B = k_m·G + k_r·pk`, `C = k_m·G +
+/// k_o·H`, derives the Keccak256 challenge and answers with `(s_m, s_r, s_o)`.
+/// Returned as `((A, B, C), (s_m, s_r, s_o))`, as `G1` points and `Fr` scalars
+/// like the other proofs; the values are not converted to affine or hex form.
+pub fn prove_ciphertext_commitment_equality<R: Rng>(
+    public_key: PublicKey,
+    ciphertext: Ciphertext,
+    commitment: Commitment,
+    message: Fr,
+    randomness: Fr,
+    opening: Fr,
+    rng: &mut R,
+) -> Result<((G1, G1, G1), (Fr, Fr, Fr)), ProofError> {
+    let generator = G1::one();
+    let pk = public_key.get_point();
+    let h = second_generator();
+
+    let k_m: Fr = Fr::random(rng);
+    let k_r: Fr = Fr::random(rng);
+    let k_o: Fr = Fr::random(rng);
+
+    let announcement_A = generator * k_r;
+    let announcement_B = generator * k_m + pk * k_r;
+    let announcement_C = generator * k_m + h * k_o;
+
+    let challenge = challenge_from_points(&[
+        pk,
+        ciphertext.points.0,
+        ciphertext.points.1,
+        commitment.0,
+        announcement_A,
+        announcement_B,
+        announcement_C,
+    ])?;
+
+    let response_m = k_m + challenge * message;
+    let response_r = k_r + challenge * randomness;
+    let response_o = k_o + challenge * opening;
+
+    Ok((
+        (announcement_A, announcement_B, announcement_C),
+        (response_m, response_r, response_o),
+    ))
+}
+
+/// Verifies a proof produced by `prove_ciphertext_commitment_equality`.
+pub fn verify_ciphertext_commitment_equality(
+    public_key: PublicKey,
+    proof: ((G1, G1, G1), (Fr, Fr, Fr)),
+    ciphertext: Ciphertext,
+    commitment: Commitment,
+) -> Result<(), ProofError> {
+    let (
+        (announcement_A, announcement_B, announcement_C),
+        (response_m, response_r, response_o),
+    ) = proof;
+
+    let generator = G1::one();
+    let pk = public_key.get_point();
+    let h = second_generator();
+
+    let challenge = challenge_from_points(&[
+        pk,
+        ciphertext.points.0,
+        ciphertext.points.1,
+        commitment.0,
+        announcement_A,
+        announcement_B,
+        announcement_C,
+    ])?;
+
+    if !(generator * response_r == announcement_A + ciphertext.points.0 * challenge
+        && generator * response_m + pk * response_r
+            == announcement_B + ciphertext.points.1 * challenge
+        && generator * response_m + h * response_o == announcement_C + commitment.0 * challenge)
+    {
+        return Err(ProofError::VerificationError);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::private::SecretKey;
+    use rand::thread_rng;
+
+    // Builds a ciphertext encrypting `value·G` under `pk` with known randomness.
+    fn encrypt_with_randomness(pk: PublicKey, value: Fr, randomness: Fr) -> Ciphertext {
+        Ciphertext {
+            pk,
+            points: (
+                G1::one() * randomness,
+                G1::one() * value + pk.get_point() * randomness,
+            ),
+        }
+    }
+
+    #[test]
+    fn test_second_generator_is_stable() {
+        assert_eq!(second_generator(), second_generator());
+    }
+
+    #[test]
+    fn test_ciphertext_commitment_equality_roundtrip() {
+        let mut rng = thread_rng();
+        let pk = PublicKey::from(&SecretKey::new(&mut rng));
+
+        let value = Fr::from_str("9").unwrap();
+        let randomness = Fr::random(&mut rng);
+        let opening = Fr::random(&mut rng);
+        let ciphertext = encrypt_with_randomness(pk, value, randomness);
+        let commitment = commit(value, opening);
+
+        let proof = prove_ciphertext_commitment_equality(
+            pk, ciphertext, commitment, value, randomness, opening, &mut rng,
+        )
+        .unwrap();
+        assert!(
+            verify_ciphertext_commitment_equality(pk, proof, ciphertext, commitment).is_ok()
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_commitment_equality_rejects_mismatched_value() {
+        let mut rng = thread_rng();
+        let pk = PublicKey::from(&SecretKey::new(&mut rng));
+
+        let randomness = Fr::random(&mut rng);
+        let opening = Fr::random(&mut rng);
+        // Ciphertext encrypts `9`, commitment hides `10`: the binding proof must fail.
+        let ciphertext = encrypt_with_randomness(pk, Fr::from_str("9").unwrap(), randomness);
+        let commitment = commit(Fr::from_str("10").unwrap(), opening);
+
+        let proof = prove_ciphertext_commitment_equality(
+            pk,
+            ciphertext,
+            commitment,
+            Fr::from_str("9").unwrap(),
+            randomness,
+            opening,
+            &mut rng,
+        )
+        .unwrap();
+        assert!(
+            verify_ciphertext_commitment_equality(pk, proof, ciphertext, commitment).is_err()
+        );
+    }
+}
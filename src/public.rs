@@ -10,6 +10,8 @@ use crate::errors::{ConversionError, ProofError};
 use rand::thread_rng;
 use sha3::{Digest, Keccak256};
 
+use std::collections::HashMap;
+
 use crate::ciphertext::*;
 use bincode::SizeLimit::Infinite;
 use bincode::rustc_serialize::{encode, decode};
@@ -145,6 +147,234 @@ impl PublicKey {
         Ok(())
     }
 
+    /// Proves that `ciphertext = (c1 = r·G, c2 = m·G + r·pk)` encrypts a bit
+    /// `m ∈ {0, 1}` without revealing which, using the disjunctive
+    /// Chaum-Pedersen OR-proof. The true branch is run honestly while the false
+    /// branch is simulated from a random response/challenge, and the Keccak256
+    /// Fiat-Shamir challenge is split so the two branch challenges sum to it.
+    /// The proof is returned as `(((A0, B0), (A1, B1)), (e0, e1, z0, z1))`, as
+    /// `G1` points and `Fr` scalars like `prove_correct_decryption_no_Merlin`;
+    /// the values are not converted to affine or hex form here.
+    pub fn prove_correct_encryption_of_bit_no_Merlin(
+        self,
+        ciphertext: Ciphertext,
+        message_bit: bool,
+        randomness: Fr,
+    ) -> Result<(((G1, G1), (G1, G1)), (Fr, Fr, Fr, Fr)), ProofError> {
+        let rng = &mut thread_rng();
+        let generator = G1::one();
+        let pk = self.get_point();
+        let c1 = ciphertext.points.0;
+        let c2 = ciphertext.points.1;
+
+        if !message_bit {
+            // Real branch `m = 0`, where `c2 = r·pk`.
+            let k: Fr = Fr::random(rng);
+            let announcement_0_G = generator * k;
+            let announcement_0_pk = pk * k;
+
+            // Simulated branch `m = 1`, where `c2 − G = r·pk`.
+            let response_1: Fr = Fr::random(rng);
+            let challenge_1: Fr = Fr::random(rng);
+            let announcement_1_G = generator * response_1 - c1 * challenge_1;
+            let announcement_1_pk = pk * response_1 - (c2 - generator) * challenge_1;
+
+            let challenge = challenge_from_points(&[
+                c1,
+                c2,
+                pk,
+                announcement_0_G,
+                announcement_0_pk,
+                announcement_1_G,
+                announcement_1_pk,
+            ])?;
+            let challenge_0 = challenge - challenge_1;
+            let response_0 = k + challenge_0 * randomness;
+
+            Ok((
+                (
+                    (announcement_0_G, announcement_0_pk),
+                    (announcement_1_G, announcement_1_pk),
+                ),
+                (challenge_0, challenge_1, response_0, response_1),
+            ))
+        } else {
+            // Simulated branch `m = 0`, where `c2 = r·pk`.
+            let response_0: Fr = Fr::random(rng);
+            let challenge_0: Fr = Fr::random(rng);
+            let announcement_0_G = generator * response_0 - c1 * challenge_0;
+            let announcement_0_pk = pk * response_0 - c2 * challenge_0;
+
+            // Real branch `m = 1`, where `c2 − G = r·pk`.
+            let k: Fr = Fr::random(rng);
+            let announcement_1_G = generator * k;
+            let announcement_1_pk = pk * k;
+
+            let challenge = challenge_from_points(&[
+                c1,
+                c2,
+                pk,
+                announcement_0_G,
+                announcement_0_pk,
+                announcement_1_G,
+                announcement_1_pk,
+            ])?;
+            let challenge_1 = challenge - challenge_0;
+            let response_1 = k + challenge_1 * randomness;
+
+            Ok((
+                (
+                    (announcement_0_G, announcement_0_pk),
+                    (announcement_1_G, announcement_1_pk),
+                ),
+                (challenge_0, challenge_1, response_0, response_1),
+            ))
+        }
+    }
+
+    /// Verifies an OR-proof produced by `prove_correct_encryption_of_bit_no_Merlin`.
+    /// Recomputes the Fiat-Shamir challenge, checks that the two branch
+    /// challenges sum to it, and checks the Chaum-Pedersen relation on both
+    /// branches. As with the other `no_Merlin` proofs this is intended for
+    /// testing; verification should happen in `Solidity`.
+    pub fn verify_correct_encryption_of_bit_no_Merlin(
+        self,
+        proof: (((G1, G1), (G1, G1)), (Fr, Fr, Fr, Fr)),
+        ciphertext: Ciphertext,
+    ) -> Result<(), ProofError> {
+        let (
+            ((announcement_0_G, announcement_0_pk), (announcement_1_G, announcement_1_pk)),
+            (challenge_0, challenge_1, response_0, response_1),
+        ) = proof;
+
+        let generator = G1::one();
+        let pk = self.get_point();
+        let c1 = ciphertext.points.0;
+        let c2 = ciphertext.points.1;
+
+        let challenge = challenge_from_points(&[
+            c1,
+            c2,
+            pk,
+            announcement_0_G,
+            announcement_0_pk,
+            announcement_1_G,
+            announcement_1_pk,
+        ])?;
+
+        if challenge_0 + challenge_1 != challenge {
+            return Err(ProofError::VerificationError);
+        }
+
+        if !(generator * response_0 == announcement_0_G + c1 * challenge_0
+            && pk * response_0 == announcement_0_pk + c2 * challenge_0
+            && generator * response_1 == announcement_1_G + c1 * challenge_1
+            && pk * response_1 == announcement_1_pk + (c2 - generator) * challenge_1)
+        {
+            return Err(ProofError::VerificationError);
+        }
+        Ok(())
+    }
+
+    /// Proves that `ciphertext_a` (under `self`) and `ciphertext_b` (under
+    /// `other`) encrypt the same plaintext `m`, as needed for confidential
+    /// transfers where a sender re-encrypts an amount to a recipient. This is a
+    /// Chaum-Pedersen equality sigma proof over the shared `m·G`: given
+    /// knowledge of `r_a`, `r_b` and `m` the prover forms the four announcements
+    /// `T1..T4`, derives the Keccak256 challenge and answers with
+    /// `(s_m, s_a, s_b)`. Returned as `((T1, T2, T3, T4), (s_m, s_a, s_b))`, as
+    /// `G1` points and `Fr` scalars like the other `no_Merlin` proofs; the
+    /// values are not converted to affine or hex form here.
+    pub fn prove_plaintext_equality_no_Merlin(
+        self,
+        other: PublicKey,
+        ciphertext_a: Ciphertext,
+        ciphertext_b: Ciphertext,
+        message: Fr,
+        randomness_a: Fr,
+        randomness_b: Fr,
+    ) -> Result<((G1, G1, G1, G1), (Fr, Fr, Fr)), ProofError> {
+        let rng = &mut thread_rng();
+        let generator = G1::one();
+        let pk_a = self.get_point();
+        let pk_b = other.get_point();
+
+        let k_m: Fr = Fr::random(rng);
+        let k_a: Fr = Fr::random(rng);
+        let k_b: Fr = Fr::random(rng);
+
+        let announcement_1 = generator * k_a;
+        let announcement_2 = generator * k_m + pk_a * k_a;
+        let announcement_3 = generator * k_b;
+        let announcement_4 = generator * k_m + pk_b * k_b;
+
+        let challenge = challenge_from_points(&[
+            pk_a,
+            pk_b,
+            ciphertext_a.points.0,
+            ciphertext_a.points.1,
+            ciphertext_b.points.0,
+            ciphertext_b.points.1,
+            announcement_1,
+            announcement_2,
+            announcement_3,
+            announcement_4,
+        ])?;
+
+        let response_m = k_m + challenge * message;
+        let response_a = k_a + challenge * randomness_a;
+        let response_b = k_b + challenge * randomness_b;
+
+        Ok((
+            (announcement_1, announcement_2, announcement_3, announcement_4),
+            (response_m, response_a, response_b),
+        ))
+    }
+
+    /// Verifies a proof produced by `prove_plaintext_equality_no_Merlin`,
+    /// recomputing the Fiat-Shamir challenge and checking the sigma relation on
+    /// both ciphertexts.
+    pub fn verify_plaintext_equality_no_Merlin(
+        self,
+        other: PublicKey,
+        proof: ((G1, G1, G1, G1), (Fr, Fr, Fr)),
+        ciphertext_a: Ciphertext,
+        ciphertext_b: Ciphertext,
+    ) -> Result<(), ProofError> {
+        let (
+            (announcement_1, announcement_2, announcement_3, announcement_4),
+            (response_m, response_a, response_b),
+        ) = proof;
+
+        let generator = G1::one();
+        let pk_a = self.get_point();
+        let pk_b = other.get_point();
+
+        let challenge = challenge_from_points(&[
+            pk_a,
+            pk_b,
+            ciphertext_a.points.0,
+            ciphertext_a.points.1,
+            ciphertext_b.points.0,
+            ciphertext_b.points.1,
+            announcement_1,
+            announcement_2,
+            announcement_3,
+            announcement_4,
+        ])?;
+
+        if !(generator * response_a == announcement_1 + ciphertext_a.points.0 * challenge
+            && generator * response_m + pk_a * response_a
+                == announcement_2 + ciphertext_a.points.1 * challenge
+            && generator * response_b == announcement_3 + ciphertext_b.points.0 * challenge
+            && generator * response_m + pk_b * response_b
+                == announcement_4 + ciphertext_b.points.1 * challenge)
+        {
+            return Err(ProofError::VerificationError);
+        }
+        Ok(())
+    }
+
     pub fn from_hex_string(hex_coords: (String, String)) -> Result<Self, ConversionError> {
         if &hex_coords.0[0..2] != "0x" || &hex_coords.1[0..2] != "0x" {
             return Err(ConversionError::IncorrectHexString);
@@ -160,6 +390,18 @@ impl PublicKey {
     }
 }
 
+// Derives a Keccak256 Fiat-Shamir challenge from an ordered list of points,
+// hashing their affine encodings exactly as the on-chain verifier does.
+pub(crate) fn challenge_from_points(points: &[G1]) -> Result<Fr, ProofError> {
+    let mut hash = Keccak256::new();
+    for point in points {
+        let affine = AffineG1::from_jacobian(*point)
+            .ok_or(ConversionError::AffineConversionFailure)?;
+        hash = hash.chain(encode(&affine, Infinite).unwrap());
+    }
+    Ok(Fr::from_slice(&hash.result()[..]).unwrap())
+}
+
 // outputs a point in hex format '0x...'
 pub fn get_point_as_hex_str(point: G1) -> Result<(String, String), ConversionError> {
     let hex_point = into_hex(point).ok_or(ConversionError::InvalidHexConversion)?;
@@ -181,6 +423,85 @@ pub fn get_fq_as_hex_str(scalar: Fq) -> Result<String, ConversionError> {
     Ok(sol_hex_scalar)
 }
 
+/// Smallest `w` with `w * w >= n`, the window size used by the baby-step
+/// giant-step discrete log.
+fn sqrt_ceil(n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+    let mut w = (n as f64).sqrt() as u64;
+    while w * w < n {
+        w += 1;
+    }
+    while w > 0 && (w - 1) * (w - 1) >= n {
+        w -= 1;
+    }
+    w
+}
+
+/// Recovers the integer message `m` from a decrypted point `M = m·G` by solving
+/// the discrete logarithm with the baby-step giant-step algorithm, assuming
+/// `m < bound`.
+///
+/// When the additive homomorphism is used for tallying (summing votes encoded
+/// as `m·G`) `decrypt` only hands back the point `m·G`; this closes the loop by
+/// returning the scalar `m` itself. Let `w = ceil(sqrt(bound))`: the baby steps
+/// tabulate `j·G` for `j` in `0..w`, and the giant steps subtract the stride
+/// `S = w·G` up to `w` times, so the search costs `O(sqrt(bound))` group
+/// operations. Returns `None` when no `m < bound` matches.
+///
+/// #Example
+/// ```
+/// extern crate rand;
+/// use elgamal_bn::public::{PublicKey, decode_discrete_log};
+/// use elgamal_bn::private::{SecretKey, };
+/// use bn::{Fr, G1, Group};
+///
+/// # fn main() {
+///     let mut csprng = rand::thread_rng();
+///     let sk = SecretKey::new(&mut csprng);
+///     let pk = PublicKey::from(&sk);
+///
+///     // Encode the count `5` as `5·G` and encrypt it.
+///     let message = G1::one() * Fr::from_str("5").unwrap();
+///     let ciphertext = pk.encrypt(&message);
+///
+///     let decryption = sk.decrypt(&ciphertext);
+///     assert_eq!(decode_discrete_log(decryption, 1 << 16), Some(5));
+/// # }
+/// ```
+pub fn decode_discrete_log(message: G1, bound: u64) -> Option<u64> {
+    let w = sqrt_ceil(bound);
+    if w == 0 {
+        return None;
+    }
+
+    let generator = G1::one();
+
+    // Baby steps: map the encoding of `j·G` to `j` for `j` in `0..w`. Starting
+    // from the identity also covers the `m = 0` case.
+    let mut baby_steps = HashMap::new();
+    let mut point = G1::zero();
+    for j in 0..w {
+        baby_steps.insert(encode(&point, Infinite).ok()?, j);
+        point = point + generator;
+    }
+
+    // `point` now equals the giant stride `S = w·G`.
+    let giant_stride = point;
+    let mut target = message;
+    for i in 0..w {
+        if let Some(&j) = baby_steps.get(&encode(&target, Infinite).ok()?) {
+            let candidate = i * w + j;
+            if candidate < bound {
+                return Some(candidate);
+            }
+        }
+        target = target - giant_stride;
+    }
+    None
+}
+
 impl From<G1> for PublicKey {
     /// Given a secret key, compute its corresponding Public key
     fn from(point: G1) -> PublicKey {
@@ -241,4 +562,114 @@ mod tests {
         let pk_from_hex = PublicKey::from_hex_string(hex_coords);
         assert!(!pk_from_hex.is_ok())
     }
+
+    // Builds a ciphertext encrypting `message` under `pk` with known randomness.
+    fn encrypt_with_randomness(pk: PublicKey, message: G1, randomness: Fr) -> Ciphertext {
+        Ciphertext {
+            pk,
+            points: (G1::one() * randomness, message + pk.get_point() * randomness),
+        }
+    }
+
+    #[test]
+    fn test_encryption_of_bit_roundtrip() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::from(&sk);
+
+        for (bit, message) in [(false, G1::zero()), (true, G1::one())].iter() {
+            let randomness = Fr::random(&mut rng);
+            let ciphertext = encrypt_with_randomness(pk, *message, randomness);
+            let proof = pk
+                .prove_correct_encryption_of_bit_no_Merlin(ciphertext, *bit, randomness)
+                .unwrap();
+            assert!(pk
+                .verify_correct_encryption_of_bit_no_Merlin(proof, ciphertext)
+                .is_ok());
+        }
+    }
+
+    #[test]
+    fn test_encryption_of_bit_rejects_tampered_response() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::from(&sk);
+
+        let randomness = Fr::random(&mut rng);
+        let ciphertext = encrypt_with_randomness(pk, G1::zero(), randomness);
+        let (announcements, (e0, e1, z0, z1)) = pk
+            .prove_correct_encryption_of_bit_no_Merlin(ciphertext, false, randomness)
+            .unwrap();
+
+        let tampered = (announcements, (e0, e1, z0 + Fr::one(), z1));
+        assert!(pk
+            .verify_correct_encryption_of_bit_no_Merlin(tampered, ciphertext)
+            .is_err());
+    }
+
+    #[test]
+    fn test_encryption_of_bit_rejects_non_bit() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::from(&sk);
+
+        // A ciphertext of `m = 2` cannot be proved to encrypt a bit.
+        let two = G1::one() * Fr::from_str("2").unwrap();
+        let randomness = Fr::random(&mut rng);
+        let ciphertext = encrypt_with_randomness(pk, two, randomness);
+        let proof = pk
+            .prove_correct_encryption_of_bit_no_Merlin(ciphertext, false, randomness)
+            .unwrap();
+        assert!(pk
+            .verify_correct_encryption_of_bit_no_Merlin(proof, ciphertext)
+            .is_err());
+    }
+
+    #[test]
+    fn test_plaintext_equality_roundtrip() {
+        let mut rng = thread_rng();
+        let pk_a = PublicKey::from(&SecretKey::new(&mut rng));
+        let pk_b = PublicKey::from(&SecretKey::new(&mut rng));
+
+        let message = Fr::from_str("7").unwrap();
+        let message_point = G1::one() * message;
+        let r_a = Fr::random(&mut rng);
+        let r_b = Fr::random(&mut rng);
+        let ciphertext_a = encrypt_with_randomness(pk_a, message_point, r_a);
+        let ciphertext_b = encrypt_with_randomness(pk_b, message_point, r_b);
+
+        let proof = pk_a
+            .prove_plaintext_equality_no_Merlin(pk_b, ciphertext_a, ciphertext_b, message, r_a, r_b)
+            .unwrap();
+        assert!(pk_a
+            .verify_plaintext_equality_no_Merlin(pk_b, proof, ciphertext_a, ciphertext_b)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_plaintext_equality_rejects_different_messages() {
+        let mut rng = thread_rng();
+        let pk_a = PublicKey::from(&SecretKey::new(&mut rng));
+        let pk_b = PublicKey::from(&SecretKey::new(&mut rng));
+
+        let r_a = Fr::random(&mut rng);
+        let r_b = Fr::random(&mut rng);
+        let ciphertext_a = encrypt_with_randomness(pk_a, G1::one() * Fr::from_str("7").unwrap(), r_a);
+        let ciphertext_b = encrypt_with_randomness(pk_b, G1::one() * Fr::from_str("8").unwrap(), r_b);
+
+        // The prover attests to `m = 7`, which is false for `ciphertext_b`.
+        let proof = pk_a
+            .prove_plaintext_equality_no_Merlin(
+                pk_b,
+                ciphertext_a,
+                ciphertext_b,
+                Fr::from_str("7").unwrap(),
+                r_a,
+                r_b,
+            )
+            .unwrap();
+        assert!(pk_a
+            .verify_plaintext_equality_no_Merlin(pk_b, proof, ciphertext_a, ciphertext_b)
+            .is_err());
+    }
 }
@@ -0,0 +1,261 @@
+#![allow(non_snake_case)]
+//! Threshold ElGamal: `t`-of-`n` secret sharing of the `SecretKey` with
+//! Feldman verifiable shares and verifiable partial decryption.
+//!
+//! A dealer splits the secret `sk` across `n` parties so that any `t` of them
+//! can jointly decrypt a `Ciphertext` while no single party ever holds `sk`.
+//! The group public key stays `f(0)·G`, so encryption is unchanged.
+
+use bn::*;
+use rand::Rng;
+
+use crate::ciphertext::Ciphertext;
+use crate::errors::ProofError;
+use crate::private::SecretKey;
+use crate::public::{challenge_from_points, PublicKey};
+
+// Maps a party index to a scalar in `Fr`, used both to evaluate the sharing
+// polynomial and to build the Lagrange coefficients.
+fn index_to_fr(index: u64) -> Fr {
+    let mut bytes = [0u8; 32];
+    bytes[24..32].copy_from_slice(&index.to_be_bytes());
+    Fr::from_slice(&bytes).unwrap()
+}
+
+/// A single party's secret share `sk_i = f(i)`.
+#[derive(Copy, Clone, Debug)]
+pub struct SecretShare {
+    pub index: u64,
+    pub value: Fr,
+}
+
+/// The output of the dealer: the group `PublicKey`, one `SecretShare` per party,
+/// and the Feldman coefficient commitments `C_j = f_j·G`.
+pub struct DealerOutput {
+    pub group_key: PublicKey,
+    pub shares: Vec<SecretShare>,
+    pub commitments: Vec<G1>,
+}
+
+/// A verifiable partial decryption `D_i = sk_i·c1` together with a
+/// discrete-log-equality proof that `log_{c1} D_i == log_G (sk_i·G)`.
+pub struct PartialDecryption {
+    pub index: u64,
+    pub point: G1,
+    pub proof: ((G1, G1), Fr),
+}
+
+/// Samples a degree `threshold − 1` polynomial `f` over `Fr` with `f(0) = sk`,
+/// hands each party `i ∈ 1..=parties` its share `f(i)`, and publishes the
+/// coefficient commitments `C_j = f_j·G` (Feldman VSS).
+pub fn deal<R: Rng>(
+    secret: &SecretKey,
+    threshold: usize,
+    parties: usize,
+    rng: &mut R,
+) -> DealerOutput {
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(secret.get_scalar());
+    for _ in 1..threshold {
+        coefficients.push(Fr::random(rng));
+    }
+
+    let commitments = coefficients
+        .iter()
+        .map(|coefficient| G1::one() * *coefficient)
+        .collect();
+
+    let shares = (1..=parties as u64)
+        .map(|index| {
+            let point = index_to_fr(index);
+            // Horner evaluation of `f(index)`.
+            let mut value = Fr::zero();
+            for coefficient in coefficients.iter().rev() {
+                value = value * point + *coefficient;
+            }
+            SecretShare { index, value }
+        })
+        .collect();
+
+    DealerOutput {
+        group_key: PublicKey::from(secret),
+        shares,
+        commitments,
+    }
+}
+
+/// Checks a share against the published commitments: `sk_i·G == Σ_j i^j·C_j`.
+pub fn verify_share(share: &SecretShare, commitments: &[G1]) -> bool {
+    let point = index_to_fr(share.index);
+    let mut expected = G1::zero();
+    let mut power = Fr::one();
+    for commitment in commitments {
+        expected = expected + *commitment * power;
+        power = power * point;
+    }
+    G1::one() * share.value == expected
+}
+
+/// Produces a party's partial decryption `D_i = sk_i·c1` with a Chaum-Pedersen
+/// discrete-log-equality proof tying it to the party's public share `sk_i·G`,
+/// reusing the Keccak256 Fiat-Shamir transcript.
+pub fn partial_decrypt<R: Rng>(
+    share: &SecretShare,
+    ciphertext: Ciphertext,
+    rng: &mut R,
+) -> Result<PartialDecryption, ProofError> {
+    let generator = G1::one();
+    let c1 = ciphertext.points.0;
+    let public_share = generator * share.value;
+    let point = c1 * share.value;
+
+    let witness: Fr = Fr::random(rng);
+    let announcement_base_G = generator * witness;
+    let announcement_base_c1 = c1 * witness;
+
+    let challenge = challenge_from_points(&[
+        generator,
+        c1,
+        public_share,
+        point,
+        announcement_base_G,
+        announcement_base_c1,
+    ])?;
+    let response = witness + challenge * share.value;
+
+    Ok(PartialDecryption {
+        index: share.index,
+        point,
+        proof: ((announcement_base_G, announcement_base_c1), response),
+    })
+}
+
+/// Verifies a partial decryption against the party's public share recovered
+/// from the commitments.
+pub fn verify_partial(
+    partial: &PartialDecryption,
+    commitments: &[G1],
+    ciphertext: Ciphertext,
+) -> Result<(), ProofError> {
+    let generator = G1::one();
+    let c1 = ciphertext.points.0;
+    let ((announcement_base_G, announcement_base_c1), response) = partial.proof;
+
+    // Recompute the party's public share `sk_i·G = Σ_j i^j·C_j`.
+    let point = index_to_fr(partial.index);
+    let mut public_share = G1::zero();
+    let mut power = Fr::one();
+    for commitment in commitments {
+        public_share = public_share + *commitment * power;
+        power = power * point;
+    }
+
+    let challenge = challenge_from_points(&[
+        generator,
+        c1,
+        public_share,
+        partial.point,
+        announcement_base_G,
+        announcement_base_c1,
+    ])?;
+
+    if !(generator * response == announcement_base_G + public_share * challenge
+        && c1 * response == announcement_base_c1 + partial.point * challenge)
+    {
+        return Err(ProofError::VerificationError);
+    }
+    Ok(())
+}
+
+/// Combines any `t` valid partial decryptions into the plaintext point
+/// `m·G = c2 − Σ_{i∈S} λ_i·D_i`, where `λ_i = Π_{j∈S, j≠i} j/(j−i)` are the
+/// Lagrange coefficients evaluated at `0`.
+pub fn combine(partials: &[PartialDecryption], ciphertext: Ciphertext) -> Result<G1, ProofError> {
+    let c2 = ciphertext.points.1;
+    let indices: Vec<Fr> = partials.iter().map(|p| index_to_fr(p.index)).collect();
+
+    let mut accumulator = G1::zero();
+    for (position, partial) in partials.iter().enumerate() {
+        let i = indices[position];
+        let mut lagrange = Fr::one();
+        for (other, j) in indices.iter().enumerate() {
+            if other == position {
+                continue;
+            }
+            let denominator = (*j - i).inverse().ok_or(ProofError::VerificationError)?;
+            lagrange = lagrange * *j * denominator;
+        }
+        accumulator = accumulator + partial.point * lagrange;
+    }
+
+    Ok(c2 - accumulator)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::public::decode_discrete_log;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_threshold_decryption_flow() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::from(&sk);
+
+        let message = G1::one() * Fr::from_str("42").unwrap();
+        let ciphertext = pk.encrypt(&message);
+
+        let dealer = deal(&sk, 2, 3, &mut rng);
+        // The group key is left untouched so existing encryption keeps working.
+        assert_eq!(dealer.group_key, pk);
+        for share in &dealer.shares {
+            assert!(verify_share(share, &dealer.commitments));
+        }
+
+        // Any `t = 2` parties recover the plaintext point.
+        let partials: Vec<PartialDecryption> = dealer.shares[..2]
+            .iter()
+            .map(|share| partial_decrypt(share, ciphertext, &mut rng).unwrap())
+            .collect();
+        for partial in &partials {
+            assert!(verify_partial(partial, &dealer.commitments, ciphertext).is_ok());
+        }
+
+        let recovered = combine(&partials, ciphertext).unwrap();
+        assert_eq!(recovered, message);
+        assert_eq!(decode_discrete_log(recovered, 1 << 16), Some(42));
+    }
+
+    #[test]
+    fn test_combine_with_different_subset() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+        let pk = PublicKey::from(&sk);
+
+        let message = G1::one() * Fr::from_str("13").unwrap();
+        let ciphertext = pk.encrypt(&message);
+
+        let dealer = deal(&sk, 2, 3, &mut rng);
+        // Use parties 1 and 3 rather than 1 and 2 to exercise the Lagrange-at-0
+        // coefficients on a different support set.
+        let subset = [dealer.shares[0], dealer.shares[2]];
+        let partials: Vec<PartialDecryption> = subset
+            .iter()
+            .map(|share| partial_decrypt(share, ciphertext, &mut rng).unwrap())
+            .collect();
+
+        assert_eq!(combine(&partials, ciphertext).unwrap(), message);
+    }
+
+    #[test]
+    fn test_verify_share_rejects_corrupted_share() {
+        let mut rng = thread_rng();
+        let sk = SecretKey::new(&mut rng);
+
+        let dealer = deal(&sk, 2, 3, &mut rng);
+        let mut corrupted = dealer.shares[0];
+        corrupted.value = corrupted.value + Fr::one();
+        assert!(!verify_share(&corrupted, &dealer.commitments));
+    }
+}